@@ -0,0 +1,287 @@
+// Wired up in src/lib.rs as `mod app; pub use app::App;`
+use std::env;
+use std::ffi::OsString;
+use std::process;
+
+use args::{Arg, ArgGroup, ArgMatches, MatchedArg};
+use errors::ClapError;
+
+/// A command line application, built up via a chain of setter methods and
+/// turned into an `ArgMatches` by `get_matches` (which parses
+/// `std::env::args_os`) or `get_matches_from` (which parses an explicit
+/// argument list, mainly useful for tests).
+#[derive(Debug, Clone, Default)]
+pub struct App<'n, 'e> {
+	pub name: String,
+	pub version: Option<&'e str>,
+	pub about: Option<&'e str>,
+	pub author: Option<&'e str>,
+	pub flags: Vec<Arg<'n, 'e>>,
+	pub opts: Vec<Arg<'n, 'e>>,
+	pub positionals: Vec<Arg<'n, 'e>>,
+	pub subcommands: Vec<App<'n, 'e>>,
+	pub groups: Vec<ArgGroup<'e>>,
+}
+
+impl<'n, 'e> App<'n, 'e> {
+	/// Start building a new `App` named `name`.
+	pub fn new(name: &str) -> Self {
+		App { name: name.to_owned(), ..Default::default() }
+	}
+
+	/// Set the version shown by `--version` and in `--help`.
+	pub fn version(mut self, version: &'e str) -> Self {
+		self.version = Some(version);
+		self
+	}
+
+	/// Set the one-line description shown in `--help`.
+	pub fn about(mut self, about: &'e str) -> Self {
+		self.about = Some(about);
+		self
+	}
+
+	/// Set the author string shown in `--help`.
+	pub fn author(mut self, author: &'e str) -> Self {
+		self.author = Some(author);
+		self
+	}
+
+	/// Add `a`, filing it as a flag, option, or positional depending on
+	/// whether it declared `short`/`long` and `takes_value`.
+	pub fn arg(mut self, a: Arg<'n, 'e>) -> Self {
+		if a.short.is_none() && a.long.is_none() {
+			self.positionals.push(a);
+		} else if a.takes_value {
+			self.opts.push(a);
+		} else {
+			self.flags.push(a);
+		}
+		self
+	}
+
+	/// Shorthand for `arg(Arg::from_usage(usage))`; see `Arg::from_usage` for
+	/// the supported syntax.
+	pub fn arg_from_usage(self, usage: &'n str) -> Self
+		where 'n: 'e
+	{
+		self.arg(Arg::from_usage(usage))
+	}
+
+	/// Add `sc` as a subcommand, dispatched to by `get_matches`/`get_matches_from`
+	/// when a non-flag token matches `sc.name` (each such token is checked
+	/// against subcommand names before being taken as a positional value).
+	pub fn subcommand(mut self, sc: App<'n, 'e>) -> Self {
+		self.subcommands.push(sc);
+		self
+	}
+
+	/// Add `g`, a named collection of this app's arg names.
+	pub fn arg_group(mut self, g: ArgGroup<'e>) -> Self {
+		self.groups.push(g);
+		self
+	}
+
+	/// Parse `std::env::args_os` (skipping the binary name), printing an
+	/// error and exiting the process on a usage error, `--help`, or
+	/// `--version`.
+	pub fn get_matches(self) -> ArgMatches<'n>
+		where 'e: 'n
+	{
+		let args: Vec<OsString> = env::args_os().skip(1).collect();
+		self.get_matches_from(args)
+	}
+
+	/// Parse an explicit argument list, printing an error and exiting the
+	/// process on a usage error, `--help`, or `--version`.
+	pub fn get_matches_from(self, args: Vec<OsString>) -> ArgMatches<'n>
+		where 'e: 'n
+	{
+		let usage = self.usage_string();
+		let mut matches = ArgMatches::new();
+		matches.set_usage(usage.clone());
+
+		// Pre-seed an (unmatched) entry for every declared arg, so
+		// `ArgMatches::possible_values` stays available for `value_t!`'s
+		// `default` arm even when the arg was never given on the command line.
+		for a in self.flags.iter().chain(self.opts.iter()).chain(self.positionals.iter()) {
+			matches.args.insert(a.name, MatchedArg {
+				present: false,
+				vals: Vec::new(),
+				possible_vals: a.possible_vals.clone(),
+			});
+		}
+
+		let mut positional_idx = 0;
+		let mut iter = args.into_iter();
+
+		while let Some(arg_os) = iter.next() {
+			let arg = arg_os.to_string_lossy().into_owned();
+
+			if arg == "--help" || arg == "-h" {
+				print!("{}", self.help_string());
+				process::exit(0);
+			}
+			if arg == "--version" || arg == "-V" {
+				println!("{} {}", self.name, self.version.unwrap_or(""));
+				process::exit(0);
+			}
+
+			if let Some(stripped) = arg.strip_prefix("--") {
+				let (name, inline_val) = match stripped.find('=') {
+					Some(i) => (&stripped[..i], Some(stripped[i + 1..].to_owned())),
+					None => (stripped, None),
+				};
+				if let Some(f) = self.flags.iter().find(|f| f.long == Some(name)) {
+					matches.args.entry(f.name).or_default().present = true;
+					continue;
+				}
+				if let Some(o) = self.opts.iter().find(|o| o.long == Some(name)) {
+					let val = match inline_val {
+						Some(v) => v,
+						None => match iter.next() {
+							Some(v) => v.to_string_lossy().into_owned(),
+							None => ClapError::usage_error(
+								format!("'--{}' requires a value", name), usage.clone()).exit(),
+						},
+					};
+					self.record(&mut matches, o, val, &usage);
+					continue;
+				}
+				ClapError::usage_error(format!("unknown flag '--{}'", name), usage.clone()).exit();
+			}
+
+			if arg.starts_with('-') && arg.len() == 2 {
+				let short = arg[1..].chars().next();
+				if let Some(f) = self.flags.iter().find(|f| f.short == short) {
+					matches.args.entry(f.name).or_default().present = true;
+					continue;
+				}
+				if let Some(o) = self.opts.iter().find(|o| o.short == short) {
+					let val = match iter.next() {
+						Some(v) => v.to_string_lossy().into_owned(),
+						None => ClapError::usage_error(
+							format!("'{}' requires a value", arg), usage.clone()).exit(),
+					};
+					self.record(&mut matches, o, val, &usage);
+					continue;
+				}
+				ClapError::usage_error(format!("unknown flag '{}'", arg), usage.clone()).exit();
+			}
+
+			if let Some(sc) = self.subcommands.iter().find(|sc| sc.name == arg) {
+				let rest: Vec<OsString> = iter.collect();
+				return sc.clone().get_matches_from(rest);
+			}
+
+			if let Some(p) = self.positionals.get(positional_idx) {
+				self.record(&mut matches, p, arg, &usage);
+				if !p.multiple {
+					positional_idx += 1;
+				}
+				continue;
+			}
+
+			ClapError::usage_error(format!("unexpected argument '{}'", arg), usage.clone()).exit();
+		}
+
+		self.check_required(&matches, &usage);
+
+		matches
+	}
+
+	fn record(&self, matches: &mut ArgMatches<'n>, a: &Arg<'n, 'e>, val: String, usage: &str)
+		where 'e: 'n
+	{
+		if let Some(ref pvs) = a.possible_vals {
+			if !pvs.contains(&val.as_str()) {
+				ClapError::value_validation(a.name, &val,
+					format!("isn't a valid value for '{}'{}", a.name, ::fmt::__possible_values_suffix(pvs)),
+					usage.to_owned()).exit();
+			}
+		}
+
+		let entry = matches.args.entry(a.name)
+			.or_insert_with(|| MatchedArg { present: false, vals: Vec::new(), possible_vals: a.possible_vals.clone() });
+		entry.present = true;
+		entry.vals.push(OsString::from(val));
+	}
+
+	// Exits with a usage error if a required arg was never matched, or a
+	// required group has none of its members matched. Only reached once a
+	// full parse completes without dispatching into a subcommand, so a
+	// subcommand's own required args/groups are checked by its own (nested)
+	// call to `get_matches_from`, not by its parent's.
+	fn check_required(&self, matches: &ArgMatches<'n>, usage: &str)
+		where 'e: 'n
+	{
+		for a in self.flags.iter().chain(self.opts.iter()).chain(self.positionals.iter()) {
+			if a.required && !matches.is_present(a.name) {
+				ClapError::usage_error(
+					format!("the following required argument was not provided: {}", a.name),
+					usage.to_owned()).exit();
+			}
+		}
+
+		for g in &self.groups {
+			if g.required && !g.args.iter().any(|name| matches.is_present(name)) {
+				ClapError::usage_error(
+					format!("one of these arguments is required: {}", g.args.join(", ")),
+					usage.to_owned()).exit();
+			}
+		}
+	}
+
+	fn usage_string(&self) -> String {
+		let mut u = format!("USAGE:\n    {}", self.name);
+		if !self.flags.is_empty() || !self.opts.is_empty() {
+			u.push_str(" [FLAGS/OPTIONS]");
+		}
+		for p in &self.positionals {
+			u.push_str(&format!(" {}", if p.required { format!("<{}>", p.name) } else { format!("[{}]", p.name) }));
+		}
+		if !self.subcommands.is_empty() {
+			u.push_str(" [SUBCOMMAND]");
+		}
+		u
+	}
+
+	fn help_string(&self) -> String {
+		let mut s = String::new();
+		if let Some(v) = self.version {
+			s.push_str(&format!("{} {}\n", self.name, v));
+		}
+		if let Some(a) = self.author {
+			s.push_str(&format!("{}\n", a));
+		}
+		if let Some(a) = self.about {
+			s.push_str(&format!("{}\n\n", a));
+		}
+		s.push_str(&self.usage_string());
+		s.push('\n');
+
+		if !self.flags.is_empty() || !self.opts.is_empty() {
+			s.push_str("\nFLAGS/OPTIONS:\n");
+			for f in self.flags.iter().chain(self.opts.iter()) {
+				s.push_str(&format!("    {}\t{}\n", arg_flags(f), get_help!(f)));
+			}
+		}
+		if !self.subcommands.is_empty() {
+			s.push_str("\nSUBCOMMANDS:\n");
+			for sc in &self.subcommands {
+				s.push_str(&format!("    {}\t{}\n", sc.name, sc.about.unwrap_or("")));
+			}
+		}
+
+		s
+	}
+}
+
+fn arg_flags(a: &Arg) -> String {
+	match (a.short, a.long) {
+		(Some(s), Some(l)) => format!("-{}, --{}", s, l),
+		(Some(s), None) => format!("-{}", s),
+		(None, Some(l)) => format!("--{}", l),
+		(None, None) => a.name.to_owned(),
+	}
+}
@@ -0,0 +1,120 @@
+// Wired up in src/args/mod.rs as `mod arg;`
+
+/// Builder for a single flag, option, or positional argument.
+///
+/// Whether an `Arg` ends up in `App::flags`, `App::opts`, or
+/// `App::positionals` is inferred from whether it declared `short`/`long`
+/// and `takes_value`, not from a separate "kind" field.
+#[derive(Debug, Clone, Default)]
+pub struct Arg<'n, 'e> {
+	pub name: &'n str,
+	pub short: Option<char>,
+	pub long: Option<&'e str>,
+	pub help: Option<&'e str>,
+	pub takes_value: bool,
+	pub required: bool,
+	pub multiple: bool,
+	pub possible_vals: Option<Vec<&'e str>>,
+}
+
+impl<'n, 'e> Arg<'n, 'e> {
+	/// Start building an `Arg` named `name`. This is also the value passed to
+	/// `ArgMatches::value_of`/`values_of` once parsing succeeds.
+	pub fn with_name(name: &'n str) -> Self {
+		Arg { name, ..Default::default() }
+	}
+
+	/// Set the single-character short flag, e.g. `-v`. Only the first
+	/// character of `s` is used.
+	pub fn short(mut self, s: &str) -> Self {
+		self.short = s.chars().next();
+		self
+	}
+
+	/// Set the long flag, e.g. `--verbose`.
+	pub fn long(mut self, l: &'e str) -> Self {
+		self.long = Some(l);
+		self
+	}
+
+	/// Set the help text shown next to this arg.
+	pub fn help(mut self, h: &'e str) -> Self {
+		self.help = Some(h);
+		self
+	}
+
+	/// Whether this arg consumes a value (an option) rather than just being
+	/// present or absent (a flag).
+	pub fn takes_value(mut self, b: bool) -> Self {
+		self.takes_value = b;
+		self
+	}
+
+	/// Whether `App::get_matches` should error out if this arg is missing.
+	pub fn required(mut self, b: bool) -> Self {
+		self.required = b;
+		self
+	}
+
+	/// Whether this arg may be given more than once / take more than one value.
+	pub fn multiple(mut self, b: bool) -> Self {
+		self.multiple = b;
+		self
+	}
+
+	/// Restrict this arg's value(s) to `pvs`; anything else is rejected at
+	/// parse time (and by `value_t!`'s `default` arm).
+	pub fn possible_values(mut self, pvs: &[&'e str]) -> Self {
+		self.possible_vals = Some(pvs.to_vec());
+		self
+	}
+}
+
+impl<'u> Arg<'u, 'u> {
+	/// Build an `Arg` from a short usage string: `[name]`/`<name>` (optional
+	/// or required positional, with a trailing `...` for `multiple`), or
+	/// `-s, --long <VALUE>` (option; `<VALUE>`/`[VALUE]` marks `takes_value`)
+	/// / `-f, --flag` (flag), each optionally followed by `'help text'`.
+	pub fn from_usage(usage: &'u str) -> Self {
+		let (spec, help) = match usage.find('\'') {
+			Some(i) => (usage[..i].trim(), Some(usage[i + 1..].trim_end_matches('\'').trim())),
+			None => (usage.trim(), None),
+		};
+
+		let mut arg = if spec.starts_with('[') || spec.starts_with('<') {
+			let required = spec.starts_with('<');
+			let multiple = spec.contains("...");
+			let name = spec.trim_end_matches("...")
+				.trim_matches(|c| c == '[' || c == ']' || c == '<' || c == '>');
+			Arg::with_name(name).required(required).multiple(multiple)
+		} else {
+			let mut short = None;
+			let mut long = None;
+			let mut takes_value = false;
+			for tok in spec.split_whitespace() {
+				let tok = tok.trim_end_matches(',');
+				if let Some(l) = tok.strip_prefix("--") {
+					long = Some(l);
+				} else if let Some(s) = tok.strip_prefix('-') {
+					short = Some(s);
+				} else if tok.starts_with('<') || tok.starts_with('[') {
+					takes_value = true;
+				}
+			}
+			let name = long.or(short).unwrap_or("");
+			let mut a = Arg::with_name(name).takes_value(takes_value);
+			if let Some(l) = long {
+				a = a.long(l);
+			}
+			if let Some(s) = short {
+				a = a.short(s);
+			}
+			a
+		};
+
+		if let Some(h) = help {
+			arg = arg.help(h);
+		}
+		arg
+	}
+}
@@ -0,0 +1,29 @@
+// Wired up in src/args/mod.rs as `mod arg_group;`
+
+/// A named collection of argument names, with an optional
+/// "at least one of these is required" constraint.
+#[derive(Debug, Clone, Default)]
+pub struct ArgGroup<'a> {
+	pub name: &'a str,
+	pub args: Vec<&'a str>,
+	pub required: bool,
+}
+
+impl<'a> ArgGroup<'a> {
+	/// Start building an `ArgGroup` named `name`.
+	pub fn with_name(name: &'a str) -> Self {
+		ArgGroup { name, ..Default::default() }
+	}
+
+	/// Set the member argument names, by the name each was given via `Arg::with_name`.
+	pub fn args(mut self, args: &[&'a str]) -> Self {
+		self.args = args.to_vec();
+		self
+	}
+
+	/// Whether at least one member of this group must be present.
+	pub fn required(mut self, b: bool) -> Self {
+		self.required = b;
+		self
+	}
+}
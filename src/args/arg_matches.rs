@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::iter::Cloned;
+use std::slice;
+
+/// What's known about a single argument once `App::get_matches_from` has run:
+/// whether the user actually supplied it (`present`), the raw values they
+/// gave (`vals`, empty if `present` is false or it's a value-less flag), and
+/// the `possible_vals` its `Arg` was declared with (if any). `App` pre-seeds
+/// an entry (with `present: false`) for every declared arg, so `possible_vals`
+/// stays available to `value_t!`'s `default` arm even when the arg was never
+/// given, without needing the `Arg` itself.
+#[derive(Debug, Clone, Default)]
+pub struct MatchedArg<'a> {
+	pub present: bool,
+	pub vals: Vec<OsString>,
+	pub possible_vals: Option<Vec<&'a str>>,
+}
+
+/// The result of `App::get_matches`. Holds whatever values the user actually
+/// supplied on the command line, keyed by argument name.
+#[derive(Debug, Clone, Default)]
+pub struct ArgMatches<'a> {
+	pub args: HashMap<&'a str, MatchedArg<'a>>,
+	usage: String,
+}
+
+impl<'a> ArgMatches<'a> {
+	pub fn new() -> Self {
+		ArgMatches {
+			args: HashMap::new(),
+			usage: String::new(),
+		}
+	}
+
+	/// The first value of `name` as a UTF-8 `&str`, if present and valid UTF-8.
+	pub fn value_of(&self, name: &str) -> Option<&str> {
+		self.args.get(name).filter(|a| a.present).and_then(|a| a.vals.first()).and_then(|v| v.to_str())
+	}
+
+	/// All the values of `name` as UTF-8 `&str`s, if present and all valid UTF-8.
+	pub fn values_of(&'a self, name: &str) -> Option<Values<'a>> {
+		self.args.get(name).filter(|a| a.present).and_then(|a| {
+			a.vals.iter().map(|v| v.to_str()).collect::<Option<Vec<_>>>()
+		}).map(Values)
+	}
+
+	/// The first value of `name` as an `&OsStr`, if present. Unlike `value_of`,
+	/// this never fails on non-UTF-8 values.
+	pub fn value_of_os(&self, name: &str) -> Option<&OsStr> {
+		self.args.get(name).filter(|a| a.present).and_then(|a| a.vals.first()).map(|v| v.as_os_str())
+	}
+
+	/// All the values of `name` as `&OsStr`s, if present. Unlike `values_of`,
+	/// this never fails on non-UTF-8 values.
+	pub fn values_of_os(&'a self, name: &str) -> Option<OsValues<'a>> {
+		self.args.get(name).filter(|a| a.present).map(|a| OsValues(a.vals.iter().map(|v| v.as_os_str()).collect()))
+	}
+
+	/// Whether `name` was supplied on the command line at all (flags included).
+	pub fn is_present(&self, name: &str) -> bool {
+		self.args.get(name).is_some_and(|a| a.present)
+	}
+
+	/// The `possible_vals` `name`'s `Arg` was declared with, if any — available
+	/// even when `name` wasn't matched, so `value_t!`'s `default` arm can
+	/// validate a default value the same way it validates a supplied one.
+	pub fn possible_values(&self, name: &str) -> Option<&[&str]> {
+		self.args.get(name).and_then(|a| a.possible_vals.as_deref())
+	}
+
+	/// The usage string to print alongside an error.
+	pub fn usage(&self) -> String {
+		self.usage.clone()
+	}
+
+	/// Set the usage string, for `App::get_matches_from` to stamp on its way out.
+	pub(crate) fn set_usage(&mut self, usage: String) {
+		self.usage = usage;
+	}
+}
+
+/// Iterator over an argument's values as `&str`, returned by `ArgMatches::values_of`.
+#[derive(Debug)]
+pub struct Values<'a>(Vec<&'a str>);
+
+impl<'a> Values<'a> {
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl<'a> IntoIterator for &'a Values<'a> {
+	type Item = &'a str;
+	type IntoIter = Cloned<slice::Iter<'a, &'a str>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter().cloned()
+	}
+}
+
+/// Iterator over an argument's values as `&OsStr`, returned by
+/// `ArgMatches::values_of_os`.
+#[derive(Debug)]
+pub struct OsValues<'a>(Vec<&'a OsStr>);
+
+impl<'a> OsValues<'a> {
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl<'a> IntoIterator for &'a OsValues<'a> {
+	type Item = &'a OsStr;
+	type IntoIter = Cloned<slice::Iter<'a, &'a OsStr>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter().cloned()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn matches_with(name: &'static str, vals: &[&str]) -> ArgMatches<'static> {
+		let mut m = ArgMatches::new();
+		m.args.insert(name, MatchedArg {
+			present: true,
+			vals: vals.iter().map(OsString::from).collect(),
+			possible_vals: None,
+		});
+		m
+	}
+
+	#[test]
+	fn value_of_returns_first_value() {
+		let m = matches_with("name", &["bob"]);
+		assert_eq!(m.value_of("name"), Some("bob"));
+		assert_eq!(m.value_of("missing"), None);
+	}
+
+	#[test]
+	fn values_of_iterates_all_values() {
+		let m = matches_with("seq", &["20", "45"]);
+		let values = m.values_of("seq").unwrap();
+		let collected: Vec<&str> = values.into_iter().collect();
+		assert_eq!(collected, vec!["20", "45"]);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn value_of_os_does_not_require_utf8() {
+		use std::os::unix::ffi::OsStringExt;
+
+		let mut m = ArgMatches::new();
+		m.args.insert("path", MatchedArg {
+			present: true,
+			vals: vec![OsString::from_vec(vec![0x66, 0x6f, 0xff, 0x6f])],
+			possible_vals: None,
+		});
+		assert!(m.value_of("path").is_none());
+		assert!(m.value_of_os("path").is_some());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn values_of_returns_none_if_any_value_is_not_utf8() {
+		use std::os::unix::ffi::OsStringExt;
+
+		let mut m = ArgMatches::new();
+		m.args.insert("path", MatchedArg {
+			present: true,
+			vals: vec![OsString::from("ok"), OsString::from_vec(vec![0x66, 0xff, 0x6f])],
+			possible_vals: None,
+		});
+		assert!(m.values_of("path").is_none());
+		assert!(m.values_of_os("path").is_some());
+	}
+
+	#[test]
+	fn values_of_os_round_trips_every_value() {
+		let m = matches_with("seq", &["a", "b"]);
+		let values = m.values_of_os("seq").unwrap();
+		let collected: Vec<&OsStr> = values.into_iter().collect();
+		assert_eq!(collected, vec![OsStr::new("a"), OsStr::new("b")]);
+	}
+
+	#[test]
+	fn possible_values_is_none_when_arg_declared_none() {
+		let m = matches_with("mode", &["fast"]);
+		assert_eq!(m.possible_values("mode"), None);
+	}
+
+	#[test]
+	fn possible_values_returns_the_declared_list() {
+		let mut m = ArgMatches::new();
+		m.args.insert("mode", MatchedArg {
+			present: true,
+			vals: vec![OsString::from("fast")],
+			possible_vals: Some(vec!["fast", "slow"]),
+		});
+		assert_eq!(m.possible_values("mode"), Some(&["fast", "slow"][..]));
+	}
+
+	#[test]
+	fn possible_values_is_available_for_an_arg_that_was_never_matched() {
+		// `App::get_matches_from` pre-seeds an entry for every declared arg
+		// (with `present: false`) precisely so this stays true, letting
+		// `value_t!`'s `default` arm validate a default value the same way
+		// it validates a supplied one.
+		let mut m = ArgMatches::new();
+		m.args.insert("mode", MatchedArg {
+			present: false,
+			vals: Vec::new(),
+			possible_vals: Some(vec!["fast", "slow"]),
+		});
+		assert_eq!(m.value_of("mode"), None);
+		assert_eq!(m.possible_values("mode"), Some(&["fast", "slow"][..]));
+	}
+
+	#[test]
+	fn is_present_distinguishes_matched_from_pre_seeded() {
+		let mut m = ArgMatches::new();
+		m.args.insert("verbose", MatchedArg { present: true, vals: Vec::new(), possible_vals: None });
+		m.args.insert("quiet", MatchedArg { present: false, vals: Vec::new(), possible_vals: None });
+		assert!(m.is_present("verbose"));
+		assert!(!m.is_present("quiet"));
+		assert!(!m.is_present("missing"));
+	}
+}
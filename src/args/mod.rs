@@ -0,0 +1,8 @@
+// Wired up in src/lib.rs as `mod args;`
+mod arg;
+mod arg_group;
+mod arg_matches;
+
+pub use self::arg::Arg;
+pub use self::arg_group::ArgGroup;
+pub use self::arg_matches::{ArgMatches, MatchedArg, OsValues, Values};
@@ -0,0 +1,137 @@
+use app::App;
+
+/// Generate a bash completion script for `app` named `bin_name`.
+///
+/// Emits one `_bin_name[_sub...]()` function per subcommand (recursively),
+/// but registers only the top-level one with `complete -F`: bash keys
+/// `complete -F` off the literal command name, and `myapp sub` is still just
+/// `myapp` as far as bash is concerned. So each function instead dispatches
+/// `myapp sub <TAB>` into the matching nested function itself, by checking
+/// `${COMP_WORDS[depth]}` against its own subcommand names before falling
+/// back to completing its own flags/options.
+pub fn generate(app: &App, bin_name: &str) -> String {
+	let mut buf = generate_fn(app, bin_name, 1);
+	buf.push_str(&format!("complete -F _{} {}\n", flatten_name(bin_name), bin_name));
+	buf
+}
+
+fn generate_fn(app: &App, bin_name: &str, depth: usize) -> String {
+	let mut buf = format!("_{}() {{\n\
+	    local cur prev cmds\n\
+	    COMPREPLY=()\n\
+	    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n\
+	    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n\n",
+		flatten_name(bin_name));
+
+	buf.push_str(&format!("    cmds=\"{}\"\n\n", subcommand_names(app).join(" ")));
+
+	if !app.subcommands.is_empty() {
+		buf.push_str(&format!("    if [[ ${{COMP_CWORD}} -gt {} ]]; then\n        case \"${{COMP_WORDS[{}]}}\" in\n", depth, depth));
+		for sc in &app.subcommands {
+			buf.push_str(&format!("            {})\n                _{}\n                return 0\n                ;;\n",
+				sc.name, flatten_name(&format!("{}_{}", bin_name, sc.name))));
+		}
+		buf.push_str("        esac\n    fi\n\n");
+	}
+
+	for (prev, pvs) in possible_vals_by_prev(app) {
+		buf.push_str(&format!("    if [[ ${{prev}} == \"{}\" ]]; then\n\
+		        COMPREPLY=( $(compgen -W \"{}\" -- ${{cur}}) )\n\
+		        return 0\n\
+		    fi\n",
+			prev, pvs.join(" ")));
+	}
+
+	buf.push_str(&format!("    COMPREPLY=( $(compgen -W \"${{cmds}} {}\" -- ${{cur}}) )\n", flag_tokens(app).join(" ")));
+	buf.push_str("    return 0\n}\n\n");
+
+	for sc in &app.subcommands {
+		buf.push_str(&generate_fn(sc, &format!("{}_{}", bin_name, sc.name), depth + 1));
+	}
+
+	buf
+}
+
+fn flatten_name(bin_name: &str) -> String {
+	bin_name.replace(['-', ' '], "_")
+}
+
+fn subcommand_names(app: &App) -> Vec<String> {
+	app.subcommands.iter().map(|sc| sc.name.clone()).collect()
+}
+
+// Every flag/option's own completion token(s): `--long` when declared, and
+// `-s` when declared.
+fn flag_tokens(app: &App) -> Vec<String> {
+	let mut v: Vec<String> = Vec::new();
+	for f in &app.flags {
+		v.extend(arg_tokens(f.long, f.short));
+	}
+	for o in &app.opts {
+		v.extend(arg_tokens(o.long, o.short));
+	}
+	v
+}
+
+fn arg_tokens(long: Option<&str>, short: Option<char>) -> Vec<String> {
+	let mut tokens = Vec::new();
+	if let Some(l) = long {
+		tokens.push(format!("--{}", l));
+	}
+	if let Some(s) = short {
+		tokens.push(format!("-{}", s));
+	}
+	tokens
+}
+
+// The token(s) bash sets `$prev` to right after a user types this option,
+// paired with its possible values. An option with neither a long nor a short
+// form can't be completed this way, so it's skipped.
+fn possible_vals_by_prev(app: &App) -> Vec<(String, Vec<String>)> {
+	app.opts.iter()
+		.filter_map(|o| o.possible_vals.as_ref().map(|pvs| {
+			(arg_tokens(o.long, o.short), pvs.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+		}))
+		.flat_map(|(prevs, pvs)| prevs.into_iter().map(move |prev| (prev, pvs.clone())))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use args::Arg;
+
+	#[test]
+	fn arg_tokens_emits_both_forms_when_both_declared() {
+		assert_eq!(arg_tokens(Some("verbose"), Some('v')), vec!["--verbose", "-v"]);
+	}
+
+	#[test]
+	fn arg_tokens_emits_a_real_short_token_for_short_only_options() {
+		// Regression: this used to fall back to the arg's bare name, which is
+		// never what bash actually sets $prev to.
+		assert_eq!(arg_tokens(None, Some('x')), vec!["-x"]);
+	}
+
+	#[test]
+	fn arg_tokens_is_empty_for_positional_only_args() {
+		assert!(arg_tokens(None, None).is_empty());
+	}
+
+	#[test]
+	fn generate_dispatches_into_the_nested_subcommand_function() {
+		// Regression: the generator used to register a separate
+		// `complete -F _myapp_sub myapp_sub`, which never fires because the
+		// user actually types `myapp sub`, not `myapp_sub`.
+		let app = App::new("myapp")
+			.arg(Arg::with_name("verbose").short("v").long("verbose"))
+			.subcommand(App::new("sub").arg(Arg::with_name("thing").long("thing").takes_value(true)));
+		let script = generate(&app, "myapp");
+
+		assert_eq!(script.matches("complete -F").count(), 1);
+		assert!(script.contains("case \"${COMP_WORDS[1]}\" in"));
+		assert!(script.contains("sub)\n                _myapp_sub\n                return 0"));
+		assert!(script.contains("_myapp_sub() {"));
+		assert!(script.contains("--thing"));
+	}
+}
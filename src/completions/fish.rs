@@ -0,0 +1,59 @@
+use app::App;
+
+/// Generate a fish completion script body for `app` named `bin_name`.
+pub fn generate(app: &App, bin_name: &str) -> String {
+	generate_for(app, bin_name, &[])
+}
+
+fn generate_for(app: &App, bin_name: &str, path: &[&str]) -> String {
+	let mut buf = String::new();
+	let condition = if path.is_empty() {
+		None
+	} else {
+		Some(format!("__fish_seen_subcommand_from {}", path.join(" ")))
+	};
+
+	for f in &app.flags {
+		buf.push_str(&complete_line(bin_name, &condition, f.short, f.long, f.help));
+	}
+	for o in &app.opts {
+		buf.push_str(&complete_line(bin_name, &condition, o.short, o.long, o.help));
+		if let Some(ref pvs) = o.possible_vals {
+			if let Some(long) = o.long {
+				buf.push_str(&format!("complete -c {} -l {} -f -a '{}'\n", bin_name, long, pvs.join(" ")));
+			}
+		}
+	}
+	for sc in &app.subcommands {
+		buf.push_str(&format!("complete -c {} -f -n '__fish_use_subcommand' -a {} -d '{}'\n",
+			bin_name, sc.name, sc.about.unwrap_or("")));
+	}
+
+	buf.push('\n');
+	let mut sub_path: Vec<&str> = path.to_vec();
+	for sc in &app.subcommands {
+		sub_path.push(&sc.name);
+		buf.push_str(&generate_for(sc, bin_name, &sub_path));
+		sub_path.pop();
+	}
+
+	buf
+}
+
+fn complete_line(bin_name: &str, condition: &Option<String>, short: Option<char>, long: Option<&str>, help: Option<&str>) -> String {
+	let mut line = format!("complete -c {}", bin_name);
+	if let Some(ref cond) = *condition {
+		line.push_str(&format!(" -n '{}'", cond));
+	}
+	if let Some(s) = short {
+		line.push_str(&format!(" -s {}", s));
+	}
+	if let Some(l) = long {
+		line.push_str(&format!(" -l {}", l));
+	}
+	if let Some(h) = help {
+		line.push_str(&format!(" -d '{}'", h));
+	}
+	line.push('\n');
+	line
+}
@@ -0,0 +1,38 @@
+// Wired up in src/lib.rs as `mod completions;`
+mod bash;
+mod zsh;
+mod fish;
+mod powershell;
+mod shell;
+
+pub use self::shell::Shell;
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use app::App;
+
+impl<'a> App<'a, 'a> {
+	/// Generate a completion script for `shell` and write it into `out_dir` as
+	/// a file named appropriately for that shell (e.g. `_myapp` for zsh).
+	///
+	/// The script is derived from the flags, options, subcommands, and
+	/// options' `possible_vals` already declared on this `App`, so it stays in
+	/// sync with the app definition without any extra bookkeeping. Positional
+	/// args are not completed.
+	pub fn gen_completions<P: AsRef<Path>>(&self, shell: Shell, out_dir: P) {
+		let (file_name, contents) = match shell {
+			Shell::Bash => (format!("{}.bash", self.name), bash::generate(self, &self.name)),
+			Shell::Zsh => (format!("_{}", self.name), zsh::generate(self, &self.name)),
+			Shell::Fish => (format!("{}.fish", self.name), fish::generate(self, &self.name)),
+			Shell::PowerShell => (format!("_{}.ps1", self.name), powershell::generate(self, &self.name)),
+		};
+
+		let path = out_dir.as_ref().join(file_name);
+		let mut file = File::create(&path)
+			.unwrap_or_else(|e| panic!("couldn't create completion file {:?}: {}", path, e));
+		file.write_all(contents.as_bytes())
+			.unwrap_or_else(|e| panic!("couldn't write completion file {:?}: {}", path, e));
+	}
+}
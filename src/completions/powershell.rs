@@ -0,0 +1,74 @@
+use app::App;
+
+/// Generate a PowerShell `Register-ArgumentCompleter` completion script for
+/// `app` named `bin_name`.
+///
+/// Unlike Bash/Zsh, a single native completer sees the whole `$commandAst`
+/// at once, so one script handles every subcommand depth: the elements the
+/// user has already typed (excluding the word being completed) are joined
+/// into a `;`-separated command path (`myapp`, `myapp;sub`, ...) and looked
+/// up in a flat `$completions` table, rather than (as before) registering a
+/// separate completer for a literal `myapp-sub` command that's never typed.
+pub fn generate(app: &App, bin_name: &str) -> String {
+	let mut buf = format!("Register-ArgumentCompleter -Native -CommandName {} -ScriptBlock {{\n\
+	    param($wordToComplete, $commandAst, $cursorPosition)\n\n\
+	    $command = @(\n\
+	        '{}'\n\
+	        $commandAst.CommandElements |\n\
+	            Select-Object -Skip 1 |\n\
+	            ForEach-Object {{ $_.Extent.Text }} |\n\
+	            Where-Object {{ $_ -ne $wordToComplete }}\n\
+	    ) -join ';'\n\n",
+		bin_name, bin_name);
+
+	buf.push_str("    $completions = @{\n");
+	push_completions_entries(app, bin_name, &mut buf);
+	buf.push_str("    }\n\n");
+
+	buf.push_str("    $completions[$command] | Where-Object { $_ -like \"$wordToComplete*\" } |\n\
+	        ForEach-Object { [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_) }\n}\n");
+
+	buf
+}
+
+fn push_completions_entries(app: &App, path: &str, buf: &mut String) {
+	let names: Vec<String> = long_flags(app).into_iter()
+		.chain(app.subcommands.iter().map(|sc| sc.name.clone()))
+		.collect();
+	buf.push_str(&format!("        '{}' = @({})\n", path,
+		names.iter().map(|n| format!("'{}'", n)).collect::<Vec<_>>().join("; ")));
+
+	for sc in &app.subcommands {
+		push_completions_entries(sc, &format!("{};{}", path, sc.name), buf);
+	}
+}
+
+fn long_flags(app: &App) -> Vec<String> {
+	let mut v: Vec<String> = app.flags.iter()
+		.filter_map(|f| f.long.map(|l| format!("--{}", l)))
+		.collect();
+	v.extend(app.opts.iter().filter_map(|o| o.long.map(|l| format!("--{}", l))));
+	v
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use args::Arg;
+
+	#[test]
+	fn generate_registers_one_completer_and_a_table_entry_per_command_path() {
+		// Regression: the generator used to recurse into a separate
+		// `Register-ArgumentCompleter -CommandName myapp-sub` script, which
+		// never fires because the user actually types `myapp sub`, not
+		// `myapp-sub`.
+		let app = App::new("myapp")
+			.arg(Arg::with_name("verbose").long("verbose"))
+			.subcommand(App::new("sub").arg(Arg::with_name("thing").long("thing").takes_value(true)));
+		let script = generate(&app, "myapp");
+
+		assert_eq!(script.matches("Register-ArgumentCompleter").count(), 1);
+		assert!(script.contains("'myapp' = @('--verbose'; 'sub')"));
+		assert!(script.contains("'myapp;sub' = @('--thing')"));
+	}
+}
@@ -0,0 +1,62 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Shells for which `App::gen_completions` can generate a completion script.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Shell {
+	/// Bourne Again `SHell` (bash)
+	Bash,
+	/// Z `SHell` (zsh)
+	Zsh,
+	/// Fish shell
+	Fish,
+	/// `PowerShell`
+	PowerShell,
+}
+
+impl FromStr for Shell {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"bash" => Ok(Shell::Bash),
+			"zsh" => Ok(Shell::Zsh),
+			"fish" => Ok(Shell::Fish),
+			"powershell" => Ok(Shell::PowerShell),
+			_ => Err(format!("'{}' is not a supported shell", s)),
+		}
+	}
+}
+
+impl fmt::Display for Shell {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", match *self {
+			Shell::Bash => "bash",
+			Shell::Zsh => "zsh",
+			Shell::Fish => "fish",
+			Shell::PowerShell => "powershell",
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn from_str_round_trips_every_shell() {
+		for shell in &[Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+			assert_eq!(shell.to_string().parse::<Shell>().unwrap(), *shell);
+		}
+	}
+
+	#[test]
+	fn from_str_is_case_insensitive() {
+		assert_eq!("PowerShell".parse::<Shell>().unwrap(), Shell::PowerShell);
+	}
+
+	#[test]
+	fn from_str_rejects_unknown_shells() {
+		assert!("cmd".parse::<Shell>().is_err());
+	}
+}
@@ -0,0 +1,103 @@
+use app::App;
+
+/// Generate a zsh `#compdef` completion script for `app` named `bin_name`.
+///
+/// Emits one `_bin_name[_sub...]` function per subcommand (recursively) and a
+/// single `#compdef` entry point. The `'*::arg:->args'` spec routes
+/// everything after the subcommand name into the `args` state, where
+/// `$line[1]` (the subcommand name) is matched to dispatch into the nested
+/// function — mirroring how zsh's own multi-level completions (e.g. `_git`)
+/// recurse into subcommands, rather than (as before) setting `$state` and
+/// never reading it back.
+pub fn generate(app: &App, bin_name: &str) -> String {
+	let mut buf = format!("#compdef {}\n\n", bin_name);
+	buf.push_str(&generate_fn(app, bin_name));
+	buf.push_str(&format!("_{} \"$@\"\n", flatten_name(bin_name)));
+	buf
+}
+
+fn generate_fn(app: &App, bin_name: &str) -> String {
+	let mut buf = format!("_{}() {{\n    _arguments \\\n", flatten_name(bin_name));
+
+	let mut specs = Vec::new();
+	for f in &app.flags {
+		for token in arg_tokens(f.long, f.short) {
+			specs.push(format!("'{}[{}]'", token, f.help.unwrap_or("")));
+		}
+	}
+	for o in &app.opts {
+		for token in arg_tokens(o.long, o.short) {
+			if let Some(ref pvs) = o.possible_vals {
+				specs.push(format!("'{}[{}]:value:({})'", token, o.help.unwrap_or(""), pvs.join(" ")));
+			} else {
+				specs.push(format!("'{}[{}]:value:'", token, o.help.unwrap_or("")));
+			}
+		}
+	}
+	if !app.subcommands.is_empty() {
+		specs.push("'1:command:->cmds'".to_owned());
+	}
+
+	buf.push_str(&specs.iter().map(|s| format!("        {} \\\n", s)).collect::<String>());
+	buf.push_str("        '*::arg:->args'\n\n");
+
+	if !app.subcommands.is_empty() {
+		buf.push_str("    case $state in\n        cmds)\n            _values 'command' \\\n");
+		for sc in &app.subcommands {
+			buf.push_str(&format!("                '{}[{}]' \\\n", sc.name, sc.about.unwrap_or("")));
+		}
+		buf.push_str("            ;;\n        args)\n            case $line[1] in\n");
+		for sc in &app.subcommands {
+			buf.push_str(&format!("                {})\n                    _{}\n                    ;;\n",
+				sc.name, flatten_name(&format!("{}_{}", bin_name, sc.name))));
+		}
+		buf.push_str("            esac\n            ;;\n    esac\n");
+	}
+
+	buf.push_str("}\n\n");
+
+	for sc in &app.subcommands {
+		buf.push_str(&generate_fn(sc, &format!("{}_{}", bin_name, sc.name)));
+	}
+
+	buf
+}
+
+fn flatten_name(bin_name: &str) -> String {
+	bin_name.replace(['-', ' '], "_")
+}
+
+// `_arguments` spec tokens for this arg: `--long` when declared, and `-s` when
+// declared (never dropped, unlike the bug this replaces).
+fn arg_tokens(long: Option<&str>, short: Option<char>) -> Vec<String> {
+	let mut tokens = Vec::new();
+	if let Some(l) = long {
+		tokens.push(format!("--{}", l));
+	}
+	if let Some(s) = short {
+		tokens.push(format!("-{}", s));
+	}
+	tokens
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use args::Arg;
+
+	#[test]
+	fn generate_dispatches_into_the_nested_subcommand_function() {
+		// Regression: the generator used to set $state to "cmds"/"args" but
+		// never read $state back to call into a nested function, so
+		// subcommand args were never completed.
+		let app = App::new("myapp")
+			.subcommand(App::new("sub").arg(Arg::with_name("thing").long("thing").takes_value(true)));
+		let script = generate(&app, "myapp");
+
+		assert_eq!(script.matches("#compdef").count(), 1);
+		assert!(script.contains("case $line[1] in"));
+		assert!(script.contains("sub)\n                    _myapp_sub\n                    ;;"));
+		assert!(script.contains("_myapp_sub() {"));
+		assert!(script.contains("--thing"));
+	}
+}
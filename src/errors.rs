@@ -0,0 +1,155 @@
+// Used by the `value_t!` and `value_t_or_exit!` macros in src/macros.rs.
+use std::error::Error;
+use std::fmt;
+
+/// Discriminant for the different ways a `value_t!`/`value_t_or_exit!` lookup
+/// can fail, so callers can `match` on it instead of parsing a message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+	/// The argument wasn't supplied on the command line (or, for
+	/// `value_of`/`values_of`, a value of it isn't valid UTF-8).
+	ArgumentNotFound,
+	/// A value of the argument (or, for `value_t!`'s `default` arm, the
+	/// default standing in for it) failed to parse or didn't match the arg's
+	/// `possible_vals`.
+	ValueValidation,
+	/// A command-line usage mistake that isn't tied to one named argument's
+	/// value: an unknown flag, a missing option value, an unexpected
+	/// argument, or a required argument/group that was never supplied.
+	UsageError,
+}
+
+/// An error produced by `App::get_matches_from` while parsing, or while
+/// pulling a typed value out of `ArgMatches` via `value_t!` or
+/// `value_t_or_exit!`.
+#[derive(Debug)]
+pub enum ClapError {
+	/// The named argument wasn't present.
+	ArgumentNotFound {
+		/// The argument's name, as passed to `value_of`/`values_of`.
+		name: String,
+		usage: String,
+		kind: ErrorKind,
+	},
+	/// A value of the named argument (or the default standing in for it)
+	/// failed validation.
+	ValueValidation {
+		/// The argument's name.
+		arg: String,
+		/// The offending raw value (for `values_of`, the first offending
+		/// element; its index is named in `cause`).
+		value: String,
+		/// What went wrong trying to use `value`.
+		cause: String,
+		usage: String,
+		kind: ErrorKind,
+	},
+	/// A usage mistake not tied to one named argument's value (see
+	/// `ErrorKind::UsageError`).
+	UsageError {
+		/// What went wrong, e.g. `"unknown flag '--nope'"`.
+		message: String,
+		usage: String,
+		kind: ErrorKind,
+	},
+}
+
+impl ClapError {
+	/// Build an `ArgumentNotFound` error for `name`.
+	pub fn argument_not_found(name: &str, usage: String) -> Self {
+		ClapError::ArgumentNotFound {
+			name: name.to_owned(),
+			usage,
+			kind: ErrorKind::ArgumentNotFound,
+		}
+	}
+
+	/// Build a `ValueValidation` error for `arg`'s offending `value`.
+	pub fn value_validation(arg: &str, value: &str, cause: String, usage: String) -> Self {
+		ClapError::ValueValidation {
+			arg: arg.to_owned(),
+			value: value.to_owned(),
+			cause,
+			usage,
+			kind: ErrorKind::ValueValidation,
+		}
+	}
+
+	/// Build a `UsageError` with `message` describing what's wrong.
+	pub fn usage_error(message: String, usage: String) -> Self {
+		ClapError::UsageError {
+			message,
+			usage,
+			kind: ErrorKind::UsageError,
+		}
+	}
+
+	/// This error's `ErrorKind`.
+	pub fn kind(&self) -> ErrorKind {
+		match *self {
+			ClapError::ArgumentNotFound { kind, .. } => kind,
+			ClapError::ValueValidation { kind, .. } => kind,
+			ClapError::UsageError { kind, .. } => kind,
+		}
+	}
+
+	/// Print this error along with the app's usage string, then exit the
+	/// process with a failure status. Used by `value_t_or_exit!`.
+	pub fn exit(&self) -> ! {
+		println!("{}\nPlease re-run with --help for more information", self);
+		::std::process::exit(1);
+	}
+}
+
+impl fmt::Display for ClapError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ClapError::ArgumentNotFound { ref name, ref usage, .. } => {
+				write!(f, "Argument \"{}\" not found or is not valid\n{}", name, usage)
+			}
+			ClapError::ValueValidation { ref value, ref cause, ref usage, .. } => {
+				write!(f, "{} {}\n{}", value, cause, usage)
+			}
+			ClapError::UsageError { ref message, ref usage, .. } => {
+				write!(f, "{}\n{}", message, usage)
+			}
+		}
+	}
+}
+
+impl Error for ClapError {
+	fn description(&self) -> &str {
+		match *self {
+			ClapError::ArgumentNotFound { .. } => "argument not found",
+			ClapError::ValueValidation { .. } => "value failed validation",
+			ClapError::UsageError { .. } => "usage error",
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn value_validation_display_does_not_duplicate_cause() {
+		let err = ClapError::value_validation("len", "5abc", "isn't a valid u32".to_owned(), "USAGE".to_owned());
+		let msg = err.to_string();
+		assert_eq!(msg.lines().next().unwrap(), "5abc isn't a valid u32");
+		assert_eq!(msg.matches("isn't a valid").count(), 1);
+	}
+
+	#[test]
+	fn argument_not_found_display() {
+		let err = ClapError::argument_not_found("len", "USAGE".to_owned());
+		assert_eq!(err.kind(), ErrorKind::ArgumentNotFound);
+		assert_eq!(err.to_string(), "Argument \"len\" not found or is not valid\nUSAGE");
+	}
+
+	#[test]
+	fn usage_error_display() {
+		let err = ClapError::usage_error("unknown flag '--nope'".to_owned(), "USAGE".to_owned());
+		assert_eq!(err.kind(), ErrorKind::UsageError);
+		assert_eq!(err.to_string(), "unknown flag '--nope'\nUSAGE");
+	}
+}
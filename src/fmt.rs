@@ -0,0 +1,11 @@
+// Wired up in src/lib.rs as `mod fmt; pub use fmt::__possible_values_suffix;`
+
+/// Format `pvs` as the `" [values: a b c]"` suffix shared by `--help` output
+/// (via `get_help!`) and `value_t!`'s validation error messages, so both
+/// describe an arg's `possible_vals` identically.
+#[doc(hidden)]
+pub fn __possible_values_suffix(pvs: &[&str]) -> String {
+	let mut pv_s = pvs.iter().fold(String::with_capacity(50), |acc, name| acc + &format!(" {}", name)[..]);
+	pv_s.shrink_to_fit();
+	format!(" [values:{}]", &pv_s[..])
+}
@@ -0,0 +1,25 @@
+//! `clap` (Command Line Argument Parser) is a library for parsing command
+//! line arguments and subcommands, built around an `App`/`Arg` builder.
+//!
+//! Enable the `yaml` feature to also get `load_yaml!`/`App::from_yaml`,
+//! for defining an `App` declaratively in a YAML file instead of in code.
+
+#[cfg(feature = "yaml")]
+extern crate yaml_rust;
+
+#[macro_use]
+mod macros;
+
+mod app;
+mod args;
+mod completions;
+mod errors;
+mod fmt;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use app::App;
+pub use args::{Arg, ArgGroup, ArgMatches, MatchedArg, OsValues, Values};
+pub use completions::Shell;
+pub use errors::{ClapError, ErrorKind};
+pub use fmt::__possible_values_suffix;
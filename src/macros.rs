@@ -4,19 +4,42 @@ macro_rules! get_help {
 		if let Some(h) = $opt.help {
 	        format!("{}{}", h,
 	            if let Some(ref pv) = $opt.possible_vals {
-	                let mut pv_s = pv.iter().fold(String::with_capacity(50), |acc, name| acc + &format!(" {}",name)[..]);
-	                pv_s.shrink_to_fit();
-	                format!(" [values:{}]", &pv_s[..])
+	                ::fmt::__possible_values_suffix(pv)
 	            }else{"".to_owned()})
 	    } else {
 	        "    ".to_owned()
-	    } 
+	    }
 	};
 }
 
+/// Load a YAML file as a `Yaml` document, for use building an `App` with `App::from_yaml`.
+///
+/// Requires the `yaml` cargo feature.
+///
+///
+/// # Example
+///
+/// ```ignore
+/// # #[macro_use]
+/// # extern crate clap;
+/// # use clap::App;
+/// # fn main() {
+/// let yml = load_yaml!("app.yml");
+/// let matches = App::from_yaml(yml).get_matches();
+/// # }
+/// ```
+#[cfg(feature = "yaml")]
+#[macro_export]
+macro_rules! load_yaml {
+	($yml:expr) => (
+		&::yaml_rust::YamlLoader::load_from_str(include_str!($yml)).expect("failed to load YAML file")[0]
+	);
+}
+
 // Thanks to bluss and flan3002 in #rust IRC
 //
 // Helps with rightward drift when iterating over something and matching each item.
+#[allow(unused_macros)]
 macro_rules! for_match {
 	($it:ident, $($p:pat => $($e:expr);+),*) => {
 		for i in $it {
@@ -30,12 +53,26 @@ macro_rules! for_match {
 }
 
 /// Convenience macro getting a typed value `T` where `T` implements `std::fmt::FrmStr`
-/// This macro returns a `Result<T,String>` which allows you as the developer to decide
-/// what you'd like to do on a failed parse. There are two types of errors, parse failures
-/// and those where the argument wasn't present (such as a non-required argument). 
+/// This macro returns a `Result<T, ClapError>` which allows you as the developer to decide
+/// what you'd like to do on a failed parse. There are two kinds of errors, parse failures
+/// (`ErrorKind::ValueValidation`) and those where the argument wasn't present such as a
+/// non-required argument (`ErrorKind::ArgumentNotFound`) - match on `err.kind()` to tell
+/// them apart.
 ///
 /// You can use it to get a single value, or a `Vec<T>` with the `values_of()`
-/// 
+///
+/// `value_of_os()`/`values_of_os()` are also accepted in place of
+/// `value_of()`/`values_of()`, for `$t` implementing `From<&OsStr>` (e.g.
+/// `PathBuf`) rather than `FromStr`, so a path that isn't valid UTF-8 doesn't
+/// have to round-trip through a lossy `&str` conversion.
+///
+/// A trailing `, default $d` substitutes `$d` (parsed as `$t`, same as a
+/// present value) instead of returning `ErrorKind::ArgumentNotFound` when the
+/// arg is absent. If the arg declares `possible_vals`, the raw value — the
+/// one the user typed, or `$d` when the arg was never given — is checked
+/// against that list before `parse::<$t>()` is attempted, so the error names
+/// the accepted values instead of just `$t`'s name.
+///
 /// **NOTE:** Be cautious, as since this a macro invocation it's not exactly like
 /// standard syntax.
 ///
@@ -49,12 +86,9 @@ macro_rules! for_match {
 /// # fn main() {
 /// let matches = App::new("myapp")
 ///               .arg_from_usage("[length] 'Set the length to use as a pos whole num, i.e. 20'")
-///				  .get_matches();
+///     .get_matches();
 /// let len = value_t!(matches.value_of("length"), u32)
-/// 				.unwrap_or_else(|e|{
-///						println!("{}",e); 
-///						std::process::exit(1)
-///					});
+///     .unwrap_or_else(|e| e.exit());
 ///
 /// println!("{} + 2: {}", len, len + 2);
 /// # }
@@ -70,14 +104,48 @@ macro_rules! for_match {
 /// # fn main() {
 /// let matches = App::new("myapp")
 ///               .arg_from_usage("[seq]... 'A sequence of pos whole nums, i.e. 20 45'")
-///				  .get_matches();
+///     .get_matches();
 /// for v in value_t!(matches.values_of("seq"), u32)
-///				.unwrap_or_else(|e|{
-///					println!("{}",e); 
-///					std::process::exit(1)
-///				}) {
-/// 	println!("{} + 2: {}", v, v + 2);
-///	}
+///    .unwrap_or_else(|e| e.exit()) {
+///  println!("{} + 2: {}", v, v + 2);
+/// }
+/// # }
+/// ```
+///
+///
+/// # Example OsStr-backed value
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate clap;
+/// # use clap::App;
+/// # use std::path::PathBuf;
+/// # fn main() {
+/// let matches = App::new("myapp")
+///               .arg_from_usage("[path] 'A path that might not be valid UTF-8'")
+///     .get_matches();
+/// let path = value_t!(matches.value_of_os("path"), PathBuf)
+///     .unwrap_or_else(|e| e.exit());
+///
+/// println!("{}", path.display());
+/// # }
+/// ```
+///
+///
+/// # Example with a default value
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate clap;
+/// # use clap::App;
+/// # fn main() {
+/// let matches = App::new("myapp")
+///               .arg_from_usage("[mode] 'Run mode'")
+///     .get_matches();
+/// let mode = value_t!(matches.value_of("mode"), String, default "fast")
+///     .unwrap_or_else(|e| e.exit());
+///
+/// println!("running in {} mode", mode);
 /// # }
 /// ```
 #[macro_export]
@@ -87,10 +155,11 @@ macro_rules! value_t {
 			Some(v) => {
 				match v.parse::<$t>() {
 					Ok(val) => Ok(val),
-					Err(_)  => Err(format!("{} isn't a valid {}",v,stringify!($t))),
+					Err(_)  => Err($crate::ClapError::value_validation(
+						$v, v, format!("isn't a valid {}", stringify!($t)), $m.usage())),
 				}
 			},
-			None => Err(format!("Argument \"{}\" not found", $v))
+			None => Err($crate::ClapError::argument_not_found($v, $m.usage()))
 		}
 	};
 	($m:ident.values_of($v:expr), $t:ty) => {
@@ -98,11 +167,14 @@ macro_rules! value_t {
 			Some(ref v) => {
 				let mut tmp = Vec::with_capacity(v.len());
 				let mut err = None;
-				for pv in v {
+				for (idx, pv) in v.into_iter().enumerate() {
 					match pv.parse::<$t>() {
 						Ok(rv) => tmp.push(rv),
 						Err(_) => {
-							err = Some(format!("{} isn't a valid {}",pv,stringify!($t)));
+							err = Some($crate::ClapError::value_validation(
+								$v, pv,
+								format!("isn't a valid {} (at index {})", stringify!($t), idx),
+								$m.usage()));
 							break
 						}
 					}
@@ -112,7 +184,49 @@ macro_rules! value_t {
 					None => Ok(tmp)
 				}
 			},
-			None => Err(format!("Argument \"{}\" not found", $v))
+			None => Err($crate::ClapError::argument_not_found($v, $m.usage()))
+		}
+	};
+	($m:ident.value_of($v:expr), $t:ty, default $d:expr) => {
+		match $m.value_of($v) {
+			Some(v) => {
+				match $m.possible_values($v) {
+					Some(pvs) if !pvs.contains(&v) => Err($crate::ClapError::value_validation(
+						$v, v, format!("isn't a valid value{}", $crate::__possible_values_suffix(pvs)), $m.usage())),
+					_ => {
+						match v.parse::<$t>() {
+							Ok(val) => Ok(val),
+							Err(_)  => Err($crate::ClapError::value_validation(
+								$v, v, format!("isn't a valid {}", stringify!($t)), $m.usage())),
+						}
+					}
+				}
+			},
+			None => {
+				match $m.possible_values($v) {
+					Some(pvs) if !pvs.contains(&$d) => Err($crate::ClapError::value_validation(
+						$v, $d, format!("isn't a valid value{}", $crate::__possible_values_suffix(pvs)), $m.usage())),
+					_ => {
+						match $d.parse::<$t>() {
+							Ok(val) => Ok(val),
+							Err(_)  => Err($crate::ClapError::value_validation(
+								$v, $d, format!("isn't a valid {}", stringify!($t)), $m.usage())),
+						}
+					}
+				}
+			}
+		}
+	};
+	($m:ident.value_of_os($v:expr), $t:ty) => {
+		match $m.value_of_os($v) {
+			Some(v) => Ok(<$t>::from(v)),
+			None => Err($crate::ClapError::argument_not_found($v, $m.usage()))
+		}
+	};
+	($m:ident.values_of_os($v:expr), $t:ty) => {
+		match $m.values_of_os($v) {
+			Some(ref v) => Ok(v.into_iter().map(<$t>::from).collect::<Vec<$t>>()),
+			None => Err($crate::ClapError::argument_not_found($v, $m.usage()))
 		}
 	};
 }
@@ -139,7 +253,7 @@ macro_rules! value_t {
 /// # fn main() {
 /// let matches = App::new("myapp")
 ///               .arg_from_usage("[length] 'Set the length to use as a pos whole num, i.e. 20'")
-///				  .get_matches();
+///     .get_matches();
 /// let len = value_t_or_exit!(matches.value_of("length"), u32);
 ///
 /// println!("{} + 2: {}", len, len + 2);
@@ -156,60 +270,153 @@ macro_rules! value_t {
 /// # fn main() {
 /// let matches = App::new("myapp")
 ///                   .arg_from_usage("[seq]... 'A sequence of pos whole nums, i.e. 20 45'")
-///					  .get_matches();
+///     .get_matches();
 /// for v in value_t_or_exit!(matches.values_of("seq"), u32) {
-/// 	println!("{} + 2: {}", v, v + 2);
-///	}
+///  println!("{} + 2: {}", v, v + 2);
+/// }
 /// # }
 /// ```
 #[macro_export]
 macro_rules! value_t_or_exit {
 	($m:ident.value_of($v:expr), $t:ty) => {
-		match $m.value_of($v) {
-			Some(v) => {
-				match v.parse::<$t>() {
-					Ok(val) => val,
-					Err(_)  => {
-						println!("{} isn't a valid {}\n{}\nPlease re-run with --help for more information",
-							v,
-							stringify!($t), 
-							$m.usage());
-						::std::process::exit(1);
-					}
-				}
-			},
-			None => {
-				println!("Argument \"{}\" not found or is not valid\n{}\nPlease re-run with --help for more information",
-					$v, 
-					$m.usage());
-				::std::process::exit(1);
-			}
+		match value_t!($m.value_of($v), $t) {
+			Ok(val) => val,
+			Err(e) => e.exit(),
 		}
 	};
 	($m:ident.values_of($v:expr), $t:ty) => {
-		match $m.values_of($v) {
-			Some(ref v) => {
-				let mut tmp = Vec::with_capacity(v.len());
-				for pv in v {
-					match pv.parse::<$t>() {
-						Ok(rv) => tmp.push(rv),
-						Err(_)  => {
-							println!("{} isn't a valid {}\n{}\nPlease re-run with --help for more information",
-								pv,
-								stringify!($t), 
-								$m.usage()); 
-							::std::process::exit(1);
-						}
-					}
-				}
-				tmp
-			},
-			None => {
-				println!("Argument \"{}\" not found or is not valid\n{}\nPlease re-run with --help for more information",
-					$v, 
-					$m.usage());
-				::std::process::exit(1);
-			}
+		match value_t!($m.values_of($v), $t) {
+			Ok(val) => val,
+			Err(e) => e.exit(),
+		}
+	};
+	($m:ident.value_of($v:expr), $t:ty, default $d:expr) => {
+		match value_t!($m.value_of($v), $t, default $d) {
+			Ok(val) => val,
+			Err(e) => e.exit(),
 		}
 	};
+	($m:ident.value_of_os($v:expr), $t:ty) => {
+		match value_t!($m.value_of_os($v), $t) {
+			Ok(val) => val,
+			Err(e) => e.exit(),
+		}
+	};
+	($m:ident.values_of_os($v:expr), $t:ty) => {
+		match value_t!($m.values_of_os($v), $t) {
+			Ok(val) => val,
+			Err(e) => e.exit(),
+		}
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use app::App;
+	use args::{ArgMatches, MatchedArg};
+	use errors::ErrorKind;
+	use std::ffi::OsString;
+	use std::path::PathBuf;
+
+	#[test]
+	fn value_t_value_of_parses_a_single_value() {
+		let matches = App::new("myapp")
+			.arg_from_usage("[length] 'len'")
+			.get_matches_from(vec!["20".into()]);
+		assert_eq!(value_t!(matches.value_of("length"), u32).unwrap(), 20);
+	}
+
+	#[test]
+	fn value_t_value_of_reports_argument_not_found_when_absent() {
+		let matches = App::new("myapp")
+			.arg_from_usage("[length] 'len'")
+			.get_matches_from(Vec::new());
+		let err = value_t!(matches.value_of("length"), u32).unwrap_err();
+		assert_eq!(err.kind(), ErrorKind::ArgumentNotFound);
+	}
+
+	#[test]
+	fn value_t_value_of_reports_value_validation_on_parse_failure() {
+		let matches = App::new("myapp")
+			.arg_from_usage("[length] 'len'")
+			.get_matches_from(vec!["nope".into()]);
+		let err = value_t!(matches.value_of("length"), u32).unwrap_err();
+		assert_eq!(err.kind(), ErrorKind::ValueValidation);
+	}
+
+	#[test]
+	fn value_t_values_of_parses_every_value() {
+		let matches = App::new("myapp")
+			.arg_from_usage("[seq]... 'seq'")
+			.get_matches_from(vec!["20".into(), "45".into()]);
+		assert_eq!(value_t!(matches.values_of("seq"), u32).unwrap(), vec![20, 45]);
+	}
+
+	#[test]
+	fn value_t_values_of_names_the_first_offending_index() {
+		let matches = App::new("myapp")
+			.arg_from_usage("[seq]... 'seq'")
+			.get_matches_from(vec!["20".into(), "nope".into()]);
+		let err = value_t!(matches.values_of("seq"), u32).unwrap_err();
+		assert!(err.to_string().contains("at index 1"));
+	}
+
+	#[test]
+	fn value_t_value_of_os_parses_from_os_str() {
+		let matches = App::new("myapp")
+			.arg_from_usage("[path] 'path'")
+			.get_matches_from(vec!["/tmp/x".into()]);
+		assert_eq!(value_t!(matches.value_of_os("path"), PathBuf).unwrap(), PathBuf::from("/tmp/x"));
+	}
+
+	#[test]
+	fn value_t_values_of_os_parses_every_value() {
+		let matches = App::new("myapp")
+			.arg_from_usage("[paths]... 'paths'")
+			.get_matches_from(vec!["a".into(), "b".into()]);
+		assert_eq!(value_t!(matches.values_of_os("paths"), PathBuf).unwrap(),
+			vec![PathBuf::from("a"), PathBuf::from("b")]);
+	}
+
+	#[test]
+	fn value_t_default_arm_uses_the_default_when_absent() {
+		let matches = App::new("myapp")
+			.arg_from_usage("[mode] 'mode'")
+			.get_matches_from(Vec::new());
+		assert_eq!(value_t!(matches.value_of("mode"), String, default "fast").unwrap(), "fast");
+	}
+
+	fn matches_with_possible_vals(val: Option<&str>, pvs: &[&'static str]) -> ArgMatches<'static> {
+		let mut m = ArgMatches::new();
+		m.args.insert("mode", MatchedArg {
+			present: val.is_some(),
+			vals: val.into_iter().map(OsString::from).collect(),
+			possible_vals: Some(pvs.to_vec()),
+		});
+		m
+	}
+
+	#[test]
+	fn value_t_default_arm_rejects_a_present_value_outside_possible_vals() {
+		let matches = matches_with_possible_vals(Some("bogus"), &["fast", "slow"]);
+		let err = value_t!(matches.value_of("mode"), String, default "fast").unwrap_err();
+		assert!(err.to_string().contains("[values: fast slow]"));
+	}
+
+	#[test]
+	fn value_t_default_arm_rejects_a_default_outside_possible_vals() {
+		// Regression: the default used to skip possible_vals validation
+		// entirely, so an absent arg would happily return an invalid default.
+		let matches = matches_with_possible_vals(None, &["fast", "slow"]);
+		let err = value_t!(matches.value_of("mode"), String, default "bogus").unwrap_err();
+		assert!(err.to_string().contains("[values: fast slow]"));
+	}
+
+	#[test]
+	fn value_t_or_exit_returns_the_value_on_success() {
+		let matches = App::new("myapp")
+			.arg_from_usage("[length] 'len'")
+			.get_matches_from(vec!["20".into()]);
+		assert_eq!(value_t_or_exit!(matches.value_of("length"), u32), 20);
+	}
 }
\ No newline at end of file
@@ -0,0 +1,143 @@
+// Requires the `yaml` cargo feature; wired up in src/lib.rs as
+// `#[cfg(feature = "yaml")] mod yaml;`
+use yaml_rust::Yaml;
+
+use app::App;
+use args::{Arg, ArgGroup};
+
+impl<'a> App<'a, 'a> {
+	/// Build an `App` from a `Yaml` document such as one loaded with `load_yaml!`.
+	///
+	/// Top level keys `name`, `version`, `about`, and `author` map onto the
+	/// corresponding `App` metadata. The `args` sequence is a list of single-key
+	/// maps, where the key is the argument's name and the value is a map of
+	/// `Arg` attributes (`short`, `long`, `help`, `takes_value`, `required`,
+	/// `multiple`, `possible_values`, ...). `subcommands` recurses into
+	/// `App::from_yaml` and `groups` is applied with `App::arg_group`.
+	///
+	/// # Panics
+	///
+	/// Panics with the offending key name if it encounters an attribute it
+	/// doesn't recognize, so a typo in a YAML file surfaces immediately rather
+	/// than silently being ignored.
+	pub fn from_yaml(doc: &'a Yaml) -> App<'a, 'a> {
+		check_keys(doc, &["name", "version", "about", "author", "args", "subcommands", "groups"],
+			"top-level key");
+
+		let mut a = App::new(yaml_str(doc, "name").unwrap_or(""));
+
+		if let Some(v) = yaml_str(doc, "version") {
+			a = a.version(v);
+		}
+		if let Some(v) = yaml_str(doc, "about") {
+			a = a.about(v);
+		}
+		if let Some(v) = yaml_str(doc, "author") {
+			a = a.author(v);
+		}
+
+		if let Some(args) = doc["args"].as_vec() {
+			for arg_yml in args {
+				let arg_map = arg_yml.as_hash().expect("each entry in 'args' must be a map");
+				if arg_map.len() != 1 {
+					panic!("each entry in 'args' must have exactly one key (the arg's name), found {}",
+						arg_map.len());
+				}
+				let (name_key, attrs) = arg_map.iter().next().expect("empty arg entry in YAML");
+				let name = name_key.as_str().expect("arg name must be a string");
+				a = a.arg(Arg::from_yaml(name, attrs));
+			}
+		}
+
+		if let Some(subcommands) = doc["subcommands"].as_vec() {
+			for sc_yml in subcommands {
+				a = a.subcommand(App::from_yaml(sc_yml));
+			}
+		}
+
+		if let Some(groups) = doc["groups"].as_vec() {
+			for grp_yml in groups {
+				a = a.arg_group(ArgGroup::from_yaml(grp_yml));
+			}
+		}
+
+		a
+	}
+}
+
+fn yaml_str<'a>(doc: &'a Yaml, key: &str) -> Option<&'a str> {
+	match &doc[key] {
+		&Yaml::BadValue => None,
+		y => Some(y.as_str().unwrap_or_else(|| panic!("'{}' must be a string", key))),
+	}
+}
+
+// Panics with the offending key name if `doc` is a map containing any key
+// outside `allowed`, so a typo (e.g. `verison:`) is caught immediately
+// instead of being silently ignored. `what` names the kind of key in the
+// panic message (e.g. "top-level key", "group attribute").
+fn check_keys(doc: &Yaml, allowed: &[&str], what: &str) {
+	if let Some(hash) = doc.as_hash() {
+		for k in hash.keys() {
+			let key = k.as_str().expect("YAML map keys must be strings");
+			if !allowed.contains(&key) {
+				panic!("unknown {} '{}'", what, key);
+			}
+		}
+	}
+}
+
+impl<'a> Arg<'a, 'a> {
+	fn from_yaml(name: &'a str, attrs: &'a Yaml) -> Arg<'a, 'a> {
+		let mut arg = Arg::with_name(name);
+
+		for (k, v) in attrs.as_hash().expect("arg attributes must be a map") {
+			let key = k.as_str().expect("arg attribute keys must be strings");
+			arg = match key {
+				"short" => arg.short(v.as_str().expect("'short' must be a string")),
+				"long" => arg.long(v.as_str().expect("'long' must be a string")),
+				"help" => arg.help(v.as_str().expect("'help' must be a string")),
+				"takes_value" => arg.takes_value(yaml_bool(v, "takes_value")),
+				"required" => arg.required(yaml_bool(v, "required")),
+				"multiple" => arg.multiple(yaml_bool(v, "multiple")),
+				"possible_values" => {
+					let pvs = v.as_vec().expect("'possible_values' must be a list");
+					arg.possible_values(&pvs.iter()
+						.map(|pv| pv.as_str().expect("possible value must be a string"))
+						.collect::<Vec<_>>())
+				}
+				_ => panic!("unknown arg attribute '{}'", key),
+			};
+		}
+
+		arg
+	}
+}
+
+impl<'a> ArgGroup<'a> {
+	fn from_yaml(doc: &'a Yaml) -> ArgGroup<'a> {
+		check_keys(doc, &["name", "args", "required"], "group attribute");
+
+		let name = doc["name"].as_str().expect("group 'name' must be a string");
+		let mut grp = ArgGroup::with_name(name);
+
+		if let Some(args) = doc["args"].as_vec() {
+			grp = grp.args(&args.iter()
+				.map(|a| a.as_str().expect("group arg must be a string"))
+				.collect::<Vec<_>>());
+		}
+		if let &Yaml::BadValue = &doc["required"] {
+			// not specified, leave the default
+		} else {
+			grp = grp.required(yaml_bool(&doc["required"], "required"));
+		}
+
+		grp
+	}
+}
+
+// `required: true` (a YAML bool) is accepted; `required: "true"` (a YAML
+// string) is a type error, not silently coerced to `true`.
+fn yaml_bool(v: &Yaml, key: &str) -> bool {
+	v.as_bool().unwrap_or_else(|| panic!("'{}' must be a bool, not a string", key))
+}